@@ -0,0 +1,257 @@
+//! Two-pass assembler: the inverse of [`crate::disasm`].
+//!
+//! Pass one walks the source assigning a word-address to every
+//! instruction and data directive, and records label definitions
+//! (`loop:`) in a symbol table. Pass two re-walks the source resolving
+//! operands - including label references - into the little-endian `u16`
+//! words that `main` loads with its `chunks(2)` reader.
+
+use std::collections::HashMap;
+
+/// Number of operand words each mnemonic takes, mirroring `disasm::opcode_info`.
+fn mnemonic_info(mnemonic: &str) -> Option<(u16, usize)> {
+    match mnemonic {
+        "halt" => Some((0, 0)),
+        "set" => Some((1, 2)),
+        "push" => Some((2, 1)),
+        "pop" => Some((3, 1)),
+        "eq" => Some((4, 3)),
+        "gt" => Some((5, 3)),
+        "jmp" => Some((6, 1)),
+        "jt" => Some((7, 2)),
+        "jf" => Some((8, 2)),
+        "add" => Some((9, 3)),
+        "mult" => Some((10, 3)),
+        "mod" => Some((11, 3)),
+        "and" => Some((12, 3)),
+        "or" => Some((13, 3)),
+        "not" => Some((14, 2)),
+        "rmem" => Some((15, 2)),
+        "wmem" => Some((16, 2)),
+        "call" => Some((17, 1)),
+        "ret" => Some((18, 0)),
+        "out" => Some((19, 1)),
+        "in" => Some((20, 1)),
+        "noop" => Some((21, 0)),
+        _ => None,
+    }
+}
+
+/// A source line with comments stripped, split into the label (if any)
+/// and the remaining mnemonic/operand tokens.
+struct Line {
+    label: Option<String>,
+    tokens: Vec<String>,
+}
+
+/// Strip a trailing `;` comment, but ignore a `;` that appears inside a
+/// `'x'` character literal (e.g. `out ';'`) so it isn't mistaken for one.
+fn strip_comment(line: &str) -> &str {
+    let mut in_literal = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '\'' => in_literal = !in_literal,
+            ';' if !in_literal => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn tokenize(source: &str) -> Result<Vec<Line>, String> {
+    let mut lines = Vec::new();
+    for raw in source.lines() {
+        let mut text = strip_comment(raw).trim();
+        let mut label = None;
+        if let Some(colon) = text.find(':') {
+            label = Some(text[..colon].trim().to_string());
+            text = text[colon + 1..].trim();
+        }
+        let tokens: Vec<String> = split_tokens(text)?;
+        if label.is_none() && tokens.is_empty() {
+            continue;
+        }
+        lines.push(Line { label, tokens });
+    }
+    Ok(lines)
+}
+
+/// Split a line into tokens, keeping `'x'` character literals intact.
+fn split_tokens(text: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '\'' {
+            chars.next();
+            let literal = chars
+                .next()
+                .ok_or_else(|| "unterminated character literal".to_string())?;
+            let close = chars.next();
+            if close != Some('\'') {
+                return Err("unterminated character literal".to_string());
+            }
+            tokens.push(format!("'{}'", literal));
+        } else {
+            let mut tok = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() {
+                    break;
+                }
+                tok.push(c);
+                chars.next();
+            }
+            tokens.push(tok);
+        }
+    }
+    Ok(tokens)
+}
+
+/// Number of words a `db`/`dw` directive's operands occupy.
+fn directive_len(tokens: &[String]) -> usize {
+    tokens.len()
+}
+
+/// Resolve an operand token to a word value: a register (`r0`..`r7`), a
+/// character literal, a decimal/hex literal, or a label reference.
+///
+/// `full_range` allows literals up to `u16::MAX`; it's set for `db`/`dw`
+/// directive words, which hold raw data, and unset for instruction
+/// operands, where the VM's own `literal_or_register` treats any value
+/// `>= 32768` as a register reference rather than a literal.
+fn resolve_operand(token: &str, symbols: &HashMap<String, u16>, full_range: bool) -> Result<u16, String> {
+    if let Some(n) = token.strip_prefix('r').and_then(|reg| reg.parse::<u16>().ok()) {
+        if n > 7 {
+            return Err(format!("register out of range: r{}", n));
+        }
+        return Ok(32768 + n);
+    }
+    if token.starts_with('\'') && token.ends_with('\'') && token.len() >= 3 {
+        let ch = token[1..token.len() - 1].chars().next().unwrap();
+        return Ok(ch as u16);
+    }
+    if let Some(hex) = token.strip_prefix("0x") {
+        let v = u16::from_str_radix(hex, 16).map_err(|_| format!("bad hex literal '{}'", token))?;
+        if !full_range && v >= 32768 {
+            return Err(format!("literal out of range: {}", token));
+        }
+        return Ok(v);
+    }
+    if let Ok(v) = token.parse::<u16>() {
+        if !full_range && v >= 32768 {
+            return Err(format!("literal out of range: {}", token));
+        }
+        return Ok(v);
+    }
+    symbols
+        .get(token)
+        .copied()
+        .ok_or_else(|| format!("undefined label '{}'", token))
+}
+
+/// Assemble `source` into the `u16` words that make up a loadable binary.
+pub fn assemble(source: &str) -> Result<Vec<u16>, String> {
+    let lines = tokenize(source)?;
+
+    // Pass one: assign addresses and record label definitions.
+    let mut symbols = HashMap::new();
+    let mut addr = 0u16;
+    for line in &lines {
+        if let Some(label) = &line.label {
+            symbols.insert(label.clone(), addr);
+        }
+        if line.tokens.is_empty() {
+            continue;
+        }
+        let mnemonic = line.tokens[0].as_str();
+        let len = if mnemonic == "db" || mnemonic == "dw" {
+            directive_len(&line.tokens[1..])
+        } else if let Some((_, arity)) = mnemonic_info(mnemonic) {
+            1 + arity
+        } else {
+            return Err(format!("unknown mnemonic '{}'", mnemonic));
+        };
+        addr = addr
+            .checked_add(len as u16)
+            .ok_or_else(|| "program too large".to_string())?;
+    }
+
+    // Pass two: resolve operands (including label references) and emit words.
+    let mut words = Vec::new();
+    for line in &lines {
+        if line.tokens.is_empty() {
+            continue;
+        }
+        let mnemonic = line.tokens[0].as_str();
+        if mnemonic == "db" || mnemonic == "dw" {
+            for token in &line.tokens[1..] {
+                words.push(resolve_operand(token, &symbols, true)?);
+            }
+            continue;
+        }
+        let (opcode, arity) = mnemonic_info(mnemonic).expect("validated in pass one");
+        if line.tokens.len() - 1 != arity {
+            return Err(format!(
+                "'{}' expects {} operand(s), got {}",
+                mnemonic,
+                arity,
+                line.tokens.len() - 1
+            ));
+        }
+        words.push(opcode);
+        for token in &line.tokens[1..] {
+            words.push(resolve_operand(token, &symbols, false)?);
+        }
+    }
+    Ok(words)
+}
+
+/// Pack assembled words into the little-endian byte stream `main` expects.
+pub fn to_bytes(words: &[u16]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(words.len() * 2);
+    for word in words {
+        bytes.push((*word & 0xFF) as u8);
+        bytes.push((*word >> 8) as u8);
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::disasm;
+
+    #[test]
+    fn round_trips_a_small_program() {
+        let source = "loop:\n  out 'A'\n  jmp loop\n";
+        let words = assemble(source).unwrap();
+        let listing = disasm::disassemble(&words).join("\n");
+        let reassembled = assemble(&listing).unwrap();
+        assert_eq!(words, reassembled);
+    }
+
+    #[test]
+    fn semicolon_character_literal_is_not_a_comment() {
+        let words = assemble("out ';'\nhalt\n").unwrap();
+        assert_eq!(words, vec![19, b';' as u16, 0]);
+    }
+
+    #[test]
+    fn unterminated_character_literal_is_an_error_not_a_panic() {
+        let err = assemble("out '\nhalt\n").unwrap_err();
+        assert!(err.contains("unterminated character literal"));
+    }
+
+    #[test]
+    fn db_directive_accepts_the_full_u16_range() {
+        let words = assemble("db 0xFFFF\n").unwrap();
+        assert_eq!(words, vec![0xFFFF]);
+    }
+
+    #[test]
+    fn instruction_operand_still_rejects_values_at_or_above_32768() {
+        let err = assemble("jmp 32768\n").unwrap_err();
+        assert!(err.contains("out of range"));
+    }
+}
@@ -0,0 +1,156 @@
+//! Interactive breakpoint/single-step debugger that gates the dispatch
+//! loop in `main`. The interpreter's semantics are untouched; this only
+//! decides when to pause and let the user inspect state before the next
+//! instruction runs.
+
+use std::collections::HashSet;
+use std::io::{self, BufRead, Write};
+
+use crate::disasm;
+use crate::snapshot;
+
+/// Tracks breakpoints and whether we're currently single-stepping.
+pub struct Debugger {
+    breakpoints: HashSet<usize>,
+    stepping: bool,
+}
+
+impl Debugger {
+    /// Starts stopped at the first instruction so breakpoints can be set
+    /// before anything runs.
+    pub fn new(breakpoints: HashSet<usize>) -> Self {
+        Debugger {
+            breakpoints,
+            stepping: true,
+        }
+    }
+
+    /// Called before every instruction dispatch. Blocks on a REPL prompt
+    /// while single-stepping or sitting on a breakpoint at `*pc`. Takes
+    /// the full VM state by mutable reference so `save`/`load` can freeze
+    /// or replace it outright.
+    pub fn poll(
+        &mut self,
+        pc: &mut usize,
+        words: &mut Vec<u16>,
+        registers: &mut [u16],
+        stack: &mut Vec<u16>,
+    ) {
+        if !self.stepping && !self.breakpoints.contains(pc) {
+            return;
+        }
+        let stdin = io::stdin();
+        loop {
+            print!("0x{:04x}> ", pc);
+            io::stdout().flush().ok();
+            let mut line = String::new();
+            if stdin.lock().read_line(&mut line).unwrap_or(0) == 0 {
+                // Stdin closed: let the program run to completion. Clearing
+                // breakpoints too means we actually free-run instead of
+                // re-entering this same blocking prompt on every future hit.
+                self.stepping = false;
+                self.breakpoints.clear();
+                return;
+            }
+            let mut tokens = line.split_whitespace();
+            match tokens.next() {
+                Some("step") | Some("s") => {
+                    self.stepping = true;
+                    return;
+                }
+                Some("continue") | Some("c") => {
+                    self.stepping = false;
+                    return;
+                }
+                Some("break") | Some("b") => match tokens.next().and_then(parse_addr) {
+                    Some(addr) => {
+                        self.breakpoints.insert(addr);
+                        println!("Breakpoint set at 0x{:04x}", addr);
+                    }
+                    None => println!("usage: break <addr>"),
+                },
+                Some("delete") => match tokens.next().and_then(parse_addr) {
+                    Some(addr) => {
+                        self.breakpoints.remove(&addr);
+                        println!("Breakpoint removed at 0x{:04x}", addr);
+                    }
+                    None => println!("usage: delete <addr>"),
+                },
+                Some("regs") => {
+                    for (i, r) in registers.iter().enumerate() {
+                        println!("r{}: 0x{:04x} ({})", i, r, r);
+                    }
+                }
+                Some("stack") => println!("{:?}", stack),
+                Some("mem") => {
+                    let addr = tokens.next().and_then(parse_addr).unwrap_or(*pc);
+                    let len = tokens.next().and_then(|s| s.parse().ok()).unwrap_or(16);
+                    for offset in 0..len {
+                        if addr + offset >= words.len() {
+                            break;
+                        }
+                        println!("0x{:04x}: 0x{:04x}", addr + offset, words[addr + offset]);
+                    }
+                }
+                Some("disasm") => {
+                    let addr = tokens.next().and_then(parse_addr).unwrap_or(*pc);
+                    let count = tokens.next().and_then(|s| s.parse().ok()).unwrap_or(10);
+                    for line in disasm::disassemble_range(words, addr, words.len())
+                        .into_iter()
+                        .take(count)
+                    {
+                        println!("{}", line);
+                    }
+                }
+                Some("save") => match tokens.next() {
+                    Some(path) => match snapshot::save(path, words, registers, stack, *pc) {
+                        Ok(()) => println!("Saved state to {}", path),
+                        Err(e) => println!("Save failed: {}", e),
+                    },
+                    None => println!("usage: save <file>"),
+                },
+                Some("load") => match tokens.next() {
+                    Some(path) => match snapshot::load(path) {
+                        Ok((new_words, new_registers, new_stack, new_pc)) => {
+                            *words = new_words;
+                            registers.copy_from_slice(&new_registers);
+                            *stack = new_stack;
+                            *pc = new_pc;
+                            println!("Loaded state from {}", path);
+                        }
+                        Err(e) => println!("Load failed: {}", e),
+                    },
+                    None => println!("usage: load <file>"),
+                },
+                Some(cmd) => println!("Unknown command '{}'", cmd),
+                None => {}
+            }
+        }
+    }
+}
+
+/// Parse an address given as either `0x1234` or a decimal number.
+pub fn parse_addr(token: &str) -> Option<usize> {
+    match token.strip_prefix("0x") {
+        Some(hex) => usize::from_str_radix(hex, 16).ok(),
+        None => token.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hex_and_decimal_addresses() {
+        assert_eq!(parse_addr("0x1a"), Some(0x1a));
+        assert_eq!(parse_addr("42"), Some(42));
+    }
+
+    #[test]
+    fn rejects_malformed_addresses() {
+        assert_eq!(parse_addr("0xzz"), None);
+        assert_eq!(parse_addr("not-a-number"), None);
+        assert_eq!(parse_addr(""), None);
+    }
+}
@@ -0,0 +1,123 @@
+//! Operand-aware disassembler.
+//!
+//! Unlike a naive per-word classifier, this walks the instruction stream
+//! starting at address 0, consuming the right number of operand words for
+//! each opcode so that operands are never misread as opcodes.
+
+/// Mnemonic and operand count for every known opcode.
+pub(crate) fn opcode_info(opcode: u16) -> Option<(&'static str, usize)> {
+    match opcode {
+        0 => Some(("halt", 0)),
+        1 => Some(("set", 2)),
+        2 => Some(("push", 1)),
+        3 => Some(("pop", 1)),
+        4 => Some(("eq", 3)),
+        5 => Some(("gt", 3)),
+        6 => Some(("jmp", 1)),
+        7 => Some(("jt", 2)),
+        8 => Some(("jf", 2)),
+        9 => Some(("add", 3)),
+        10 => Some(("mult", 3)),
+        11 => Some(("mod", 3)),
+        12 => Some(("and", 3)),
+        13 => Some(("or", 3)),
+        14 => Some(("not", 2)),
+        15 => Some(("rmem", 2)),
+        16 => Some(("wmem", 2)),
+        17 => Some(("call", 1)),
+        18 => Some(("ret", 0)),
+        19 => Some(("out", 1)),
+        20 => Some(("in", 1)),
+        21 => Some(("noop", 0)),
+        _ => None,
+    }
+}
+
+/// Render a single operand word, using register names for register
+/// addresses and a decimal literal otherwise.
+fn format_operand(word: u16) -> String {
+    if word >= 32768 {
+        format!("r{}", word - 32768)
+    } else {
+        format!("{}", word)
+    }
+}
+
+/// Render an `out` operand, preferring a character literal when the value
+/// is printable ASCII so the listing reads like the text it prints.
+fn format_out_operand(word: u16) -> String {
+    if word >= 32768 {
+        format!("r{}", word - 32768)
+    } else if word < 128 && !(word as u8 as char).is_control() {
+        format!("'{}'", word as u8 as char)
+    } else {
+        format!("{}", word)
+    }
+}
+
+/// Disassemble `words` into a listing, one line per instruction (or, when
+/// the stream can't be decoded as a valid opcode, one line per raw word).
+///
+/// `wmem` can turn later regions of memory into data that is never meant
+/// to be executed, so any word that isn't a recognised opcode falls back
+/// to a `db 0xNNNN` line rather than aborting the whole listing.
+pub fn disassemble(words: &[u16]) -> Vec<String> {
+    disassemble_range(words, 0, words.len())
+}
+
+/// Like [`disassemble`], but starts at word address `start` and stops at
+/// `end` (both absolute into `words`). Used by the debugger's `disasm`
+/// command to list a window around an arbitrary address.
+pub fn disassemble_range(words: &[u16], start: usize, end: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut pc = start;
+    let end = end.min(words.len());
+    while pc < end {
+        let opcode = words[pc];
+        match opcode_info(opcode) {
+            Some((mnemonic, arity)) if pc + arity < words.len() => {
+                let mut parts = vec![mnemonic.to_string()];
+                for i in 0..arity {
+                    let operand = words[pc + 1 + i];
+                    let rendered = if mnemonic == "out" && i == 0 {
+                        format_out_operand(operand)
+                    } else {
+                        format_operand(operand)
+                    };
+                    parts.push(rendered);
+                }
+                lines.push(format!("0x{:04x}: {}", pc, parts.join(" ")));
+                pc += 1 + arity;
+            }
+            _ => {
+                lines.push(format!("0x{:04x}: db 0x{:04x}", pc, words[pc]));
+                pc += 1;
+            }
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn walks_operands_instead_of_misreading_them_as_opcodes() {
+        // jmp 19 ; the operand 19 must not be decoded as `out`.
+        let lines = disassemble(&[6, 19]);
+        assert_eq!(lines, vec!["0x0000: jmp 19".to_string()]);
+    }
+
+    #[test]
+    fn formats_printable_out_operands_as_character_literals() {
+        let lines = disassemble(&[19, b'A' as u16]);
+        assert_eq!(lines, vec!["0x0000: out 'A'".to_string()]);
+    }
+
+    #[test]
+    fn falls_back_to_db_for_undecodable_high_words() {
+        let lines = disassemble(&[0xFFFF]);
+        assert_eq!(lines, vec!["0x0000: db 0xffff".to_string()]);
+    }
+}
@@ -0,0 +1,132 @@
+//! Buffered input source for the `in` opcode: drains a scripted command
+//! file line-by-line, falls back to interactive stdin once the script is
+//! exhausted, and mirrors every character consumed to a replay log.
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Lines, Write};
+
+pub struct InputSource {
+    script: Option<Lines<BufReader<File>>>,
+    buffer: VecDeque<u8>,
+    log: Option<File>,
+}
+
+impl InputSource {
+    pub fn new(script_path: Option<&str>, log_path: Option<&str>) -> io::Result<Self> {
+        let script = match script_path {
+            Some(path) => Some(BufReader::new(File::open(path)?).lines()),
+            None => None,
+        };
+        let log = match log_path {
+            Some(path) => Some(File::create(path)?),
+            None => None,
+        };
+        Ok(InputSource {
+            script,
+            buffer: VecDeque::new(),
+            log,
+        })
+    }
+
+    /// Return the next character the `in` opcode should see. The spec
+    /// guarantees that once input starts it continues to a newline, so a
+    /// whole line is queued up at a time.
+    pub fn next_byte(&mut self) -> u8 {
+        if self.buffer.is_empty() {
+            self.fill();
+        }
+        let byte = self.buffer.pop_front().unwrap_or(b'\n');
+        if let Some(log) = &mut self.log {
+            log.write_all(&[byte]).ok();
+        }
+        byte
+    }
+
+    /// Queue up the next line, from the script if one is still open and
+    /// has lines left, otherwise from interactive stdin.
+    fn fill(&mut self) {
+        let scripted = match &mut self.script {
+            Some(lines) => lines.next().and_then(Result::ok),
+            None => None,
+        };
+        let line = match scripted {
+            Some(line) => line,
+            None => {
+                self.script = None;
+                let mut line = String::new();
+                io::stdin().read_line(&mut line).ok();
+                line.trim_end_matches('\n').to_string()
+            }
+        };
+        self.buffer.extend(line.into_bytes());
+        self.buffer.push_back(b'\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// A path under the OS temp dir unique to this test, so parallel test
+    /// threads don't clobber each other's script/log file.
+    fn scratch_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("synacor-input-test-{}-{}.txt", std::process::id(), name))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn drains_scripted_lines_in_order_with_trailing_newlines() {
+        let script_path = scratch_path("script");
+        fs::write(&script_path, "look\ninventory\n").unwrap();
+
+        let mut input = InputSource::new(Some(&script_path), None).unwrap();
+        let mut seen = Vec::new();
+        for _ in 0.."look\ninventory\n".len() {
+            seen.push(input.next_byte());
+        }
+        fs::remove_file(&script_path).ok();
+
+        assert_eq!(seen, b"look\ninventory\n");
+    }
+
+    #[test]
+    fn mirrors_consumed_bytes_to_the_replay_log() {
+        let script_path = scratch_path("script-logged");
+        let log_path = scratch_path("log");
+        fs::write(&script_path, "yes\n").unwrap();
+
+        {
+            let mut input = InputSource::new(Some(&script_path), Some(&log_path)).unwrap();
+            for _ in 0.."yes\n".len() {
+                input.next_byte();
+            }
+        }
+        let logged = fs::read(&log_path).unwrap();
+        fs::remove_file(&script_path).ok();
+        fs::remove_file(&log_path).ok();
+
+        assert_eq!(logged, b"yes\n");
+    }
+
+    #[test]
+    fn falls_back_to_stdin_once_the_script_is_exhausted() {
+        let script_path = scratch_path("short-script");
+        fs::write(&script_path, "go\n").unwrap();
+
+        let mut input = InputSource::new(Some(&script_path), None).unwrap();
+        assert!(input.script.is_some());
+        for _ in 0.."go\n".len() {
+            input.next_byte();
+        }
+        // Script is drained; the next fill reads stdin (EOF in test runs)
+        // and must clear `script` rather than re-reading the script file.
+        input.next_byte();
+        fs::remove_file(&script_path).ok();
+
+        assert!(input.script.is_none());
+    }
+}
@@ -2,6 +2,18 @@ use std::fs::File;
 use std::io::prelude::*;
 use std::env;
 
+mod asm;
+mod debugger;
+mod disasm;
+mod input;
+mod snapshot;
+mod stats;
+
+use debugger::Debugger;
+use input::InputSource;
+use stats::Stats;
+use std::collections::HashSet;
+
 fn literal_or_register(addr: u16, registers: &[u16]) -> u16 {
     if addr >= 32768 {
         let x = registers[addr as usize - 32768];
@@ -17,93 +29,68 @@ fn register(addr: u16, registers: &mut [u16]) -> &mut u16 {
     &mut registers[reg as usize]
 }
 
-fn dissasemble(word: u16) -> String {
-    match word {
-        // halt: 0
-        // stop execution and terminate the program
-        0 => "halt".into(),
-        // set: 1 a b
-        // set register <a> to the value of <b>
-        1 => "set".into(),
-        // push: 2 a
-        // push <a> onto the stack
-        2 => "push".into(),
-        // pop: 3 a
-        // remove the top element from the stack and write it into <a>; empty stack = error
-        3 => "pop".into(),
-        // eq: 4 a b c
-        // set <a> to 1 if <b> is equal to <c>; set it to 0 otherwise
-        4 => "eq".into(),
-        // gt: 5 a b c
-        5 => "gt".into(),
-        // set <a> to 1 if <b> is greater than <c>; set it to 0 otherwise
-        // jmp: 6 a
-        // jump to <a>
-        6 => "jmp".into(),
-        // jt: 7 a b
-        // if <a> is nonzero, jump to <b>
-        7 => "jump-true".into(),
-        // jf: 8 a b
-        // if <a> is zero, jump to <b>
-        8 => "jump-false".into(),
-        // add: 9 a b c
-        // assign into <a> the sum of <b> and <c> (modulo 32768)
-        9 => "add".into(),
-        // mult: 10 a b c
-        // store into <a> the product of <b> and <c> (modulo 32768)
-        10 => "mult".into(),
-        // mod: 11 a b c
-        // store into <a> the remainder of <b> divided by <c>
-        11 => "mod".into(),
-        // and: 12 a b c
-        // stores into <a> the bitwise and of <b> and <c>
-        12 => "and".into(),
-        // or: 13 a b c
-        // stores into <a> the bitwise or of <b> and <c>
-        13 => "or".into(),
-        // not: 14 a b
-        // stores 15-bit bitwise inverse of <b> in <a>
-        14 => "not".into(),
-        // rmem: 15 a b
-        // read memory at address <b> and write it to <a>
-        15 => "rmem".into(),
-        // wmem: 16 a b
-        // write the value from <b> into memory at address <a>
-        16 => "wmem".into(),
-        // call: 17 a
-        // write the address of the next instruction to the stack and jump to <a>
-        17 => "call".into(),
-        // ret: 18
-        // remove the top element from the stack and jump to it; empty stack = halt
-        18 => "ret".into(),
-        // out: 19 a
-        // write the character represented by ascii code <a> to the terminal
-        19 => "out".into(),
-        // in: 20 a
-        // read a character from the terminal and write its ascii code to <a>; it can be assumed that once input starts, it will continue until a newline is encountered; this means that you can safely read whole lines from the keyboard and trust that they will be fully read
-        // todo
-        // noop: 21
-        // no operation
-        21 => "nop".into(),
-        32768 => "Register 0".into(),
-        32769 => "Register 1".into(),
-        32770 => "Register 2".into(),
-        32771 => "Register 3".into(),
-        32772 => "Register 4".into(),
-        32773 => "Register 5".into(),
-        32774 => "Register 6".into(),
-        32775 => "Register 7".into(),
-        x if x < 128 => format!("printable {} ({})", x, (x as u8 as char).escape_default()),
-        x => format!("literal {}", x),
-    }
+fn assemble_file(input: &str, output: &str) {
+    let mut file = File::open(input).expect("Can't open file");
+    let mut source = String::new();
+    file.read_to_string(&mut source).expect("Can't read");
+    let words = asm::assemble(&source).expect("Assembly failed");
+    let mut out = File::create(output).expect("Can't create output file");
+    out.write_all(&asm::to_bytes(&words)).expect("Can't write");
+    println!("Assembled {} words to {}", words.len(), output);
 }
 
 fn main() {
     let args: Vec<String> = env::args().collect();
-    if args.len() != 2 {
+    if args.len() == 4 && args[1] == "asm" {
+        assemble_file(&args[2], &args[3]);
+        return;
+    }
+    if args.len() < 2 {
         panic!("Need bin argument");
     }
     let filename = &args[1];
+    let mut debug = false;
+    let mut breakpoints = HashSet::new();
+    let mut max_cycles = None;
+    let mut tick_period = 65536u32;
+    let mut input_script = None;
+    let mut input_log = None;
+    let mut rest = args[2..].iter();
+    while let Some(flag) = rest.next() {
+        match flag.as_str() {
+            "--debug" => debug = true,
+            "--break" => {
+                let addr = rest
+                    .next()
+                    .and_then(|s| debugger::parse_addr(s))
+                    .expect("--break needs an address");
+                breakpoints.insert(addr);
+                // A breakpoint is pointless without the debugger attached
+                // to stop on it.
+                debug = true;
+            }
+            "--max-cycles" => {
+                max_cycles = Some(
+                    rest.next()
+                        .and_then(|s| s.parse().ok())
+                        .expect("--max-cycles needs a number"),
+                );
+            }
+            "--tick-period" => {
+                tick_period = rest
+                    .next()
+                    .and_then(|s| s.parse().ok())
+                    .expect("--tick-period needs a number");
+            }
+            "--input" => {
+                input_script = Some(rest.next().expect("--input needs a file").clone());
+            }
+            "--input-log" => {
+                input_log = Some(rest.next().expect("--input-log needs a file").clone());
+            }
+            other => panic!("Unrecognised argument {}", other),
+        }
+    }
     println!("Loading {}", filename);
     let mut file = File::open(filename).expect("Can't open file");
     let mut data: Vec<u8> = Vec::new();
@@ -113,19 +100,42 @@ fn main() {
         .map(|x| x[0] as u16 + (x[1] as u16 * 256))
         .collect();
     println!("Read {} words", words.len());
-    for (i, v) in words.iter().enumerate() {
-        println!("0x{0:04x}: 0x{1:04x} (0d{1}) ; {2}", i, v, dissasemble(*v));
+    for line in disasm::disassemble(&words) {
+        println!("{}", line);
     }
     let mut registers = vec![0u16; 8];
     let mut stack: Vec<u16> = Vec::new();
     let mut pc = 0;
+    let mut dbg = if debug {
+        Some(Debugger::new(breakpoints))
+    } else {
+        None
+    };
+    let mut stats = Stats::new(max_cycles, tick_period);
+    let mut input = InputSource::new(input_script.as_deref(), input_log.as_deref())
+        .expect("Can't open input script/log");
     loop {
+        if stats.over_budget() {
+            println!(
+                "Cycle budget of {} exhausted at 0x{:04x}",
+                stats.max_cycles().unwrap(),
+                pc
+            );
+            stats.report();
+            return;
+        }
+        if let Some(dbg) = &mut dbg {
+            dbg.poll(&mut pc, &mut words, &mut registers, &mut stack);
+        }
         let op = words[pc];
-        // println!("Executing {} ({}) at 0x{:04x}", op, dissasemble(op), pc);
+        stats.record(op);
         match op {
             // halt: 0
             // stop execution and terminate the program
-            0 => return,
+            0 => {
+                stats.report();
+                return;
+            }
             // set: 1 a b
             // set register <a> to the value of <b>
             1 => {
@@ -285,9 +295,7 @@ fn main() {
             // read a character from the terminal and write its ascii code to <a>; it can be assumed that once input starts, it will continue until a newline is encountered; this means that you can safely read whole lines from the keyboard and trust that they will be fully read
             20 => {
                 let a = register(words[pc + 1], &mut registers);
-                println!("Reading...");
-                *a = std::io::stdin().bytes().next().unwrap().unwrap() as u16;
-                println!("Read {}", *a);
+                *a = input.next_byte() as u16;
                 pc = pc + 2;
             }
             // noop: 21
@@ -0,0 +1,117 @@
+//! Save/restore of full VM state, so a long-running session (e.g. the
+//! text-adventure half of the challenge) can be frozen and resumed in a
+//! fresh process instead of being replayed from the start.
+
+use std::fs::File;
+use std::io::{self, Read, Write};
+
+/// Number of registers the VM has; any snapshot with a different count is
+/// from a stale format or a foreign file and is rejected by [`load`].
+const REGISTER_COUNT: usize = 8;
+
+/// The VM state restored by [`load`]: `(words, registers, stack, pc)`.
+type SnapshotState = (Vec<u16>, Vec<u16>, Vec<u16>, usize);
+
+/// Write `words`, `registers`, `stack` and `pc` to `path` as a compact
+/// little-endian binary file.
+pub fn save(path: &str, words: &[u16], registers: &[u16], stack: &[u16], pc: usize) -> io::Result<()> {
+    let mut out = File::create(path)?;
+    write_words(&mut out, words)?;
+    write_words(&mut out, registers)?;
+    write_words(&mut out, stack)?;
+    out.write_all(&(pc as u32).to_le_bytes())?;
+    Ok(())
+}
+
+/// Restore `(words, registers, stack, pc)` from a file written by [`save`].
+///
+/// Returns an error (rather than letting the caller panic) if the
+/// register count doesn't match [`REGISTER_COUNT`], which catches stale
+/// or foreign snapshot files.
+pub fn load(path: &str) -> io::Result<SnapshotState> {
+    let mut file = File::open(path)?;
+    let words = read_words(&mut file)?;
+    let registers = read_words(&mut file)?;
+    if registers.len() != REGISTER_COUNT {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "snapshot has {} registers, expected {}",
+                registers.len(),
+                REGISTER_COUNT
+            ),
+        ));
+    }
+    let stack = read_words(&mut file)?;
+    let mut pc_bytes = [0u8; 4];
+    file.read_exact(&mut pc_bytes)?;
+    let pc = u32::from_le_bytes(pc_bytes) as usize;
+    Ok((words, registers, stack, pc))
+}
+
+fn write_words(out: &mut File, words: &[u16]) -> io::Result<()> {
+    out.write_all(&(words.len() as u32).to_le_bytes())?;
+    for word in words {
+        out.write_all(&word.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+fn read_words(file: &mut File) -> io::Result<Vec<u16>> {
+    let mut len_bytes = [0u8; 4];
+    file.read_exact(&mut len_bytes)?;
+    let len = u32::from_le_bytes(len_bytes) as usize;
+    let mut words = Vec::with_capacity(len);
+    for _ in 0..len {
+        let mut word_bytes = [0u8; 2];
+        file.read_exact(&mut word_bytes)?;
+        words.push(u16::from_le_bytes(word_bytes));
+    }
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// A path under the OS temp dir unique to this test, so parallel test
+    /// threads don't clobber each other's snapshot file.
+    fn scratch_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("synacor-snapshot-test-{}-{}.bin", std::process::id(), name))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn round_trips_words_registers_stack_and_pc() {
+        let path = scratch_path("round-trip");
+        let words = vec![21, 21, 19, 9, 0];
+        let registers = vec![1, 2, 3, 4, 5, 6, 7, 8];
+        let stack = vec![42, 1337];
+        save(&path, &words, &registers, &stack, 3).unwrap();
+
+        let (loaded_words, loaded_registers, loaded_stack, loaded_pc) = load(&path).unwrap();
+        fs::remove_file(&path).ok();
+
+        assert_eq!(loaded_words, words);
+        assert_eq!(loaded_registers, registers);
+        assert_eq!(loaded_stack, stack);
+        assert_eq!(loaded_pc, 3);
+    }
+
+    #[test]
+    fn rejects_snapshot_with_mismatched_register_count() {
+        let path = scratch_path("bad-register-count");
+        let words = vec![0];
+        let registers = vec![1, 2, 3];
+        let stack = vec![];
+        save(&path, &words, &registers, &stack, 0).unwrap();
+
+        let err = load(&path).unwrap_err();
+        fs::remove_file(&path).ok();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
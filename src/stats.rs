@@ -0,0 +1,97 @@
+//! Cycle accounting: a monotonic instruction counter with an optional
+//! execution budget, a wrap-around tick register for profiling hot loops,
+//! and a per-opcode histogram reported on halt.
+
+use crate::disasm;
+
+pub struct Stats {
+    total: u64,
+    histogram: [u64; 22],
+    tick: u32,
+    tick_period: u32,
+    max_cycles: Option<u64>,
+}
+
+impl Stats {
+    pub fn new(max_cycles: Option<u64>, tick_period: u32) -> Self {
+        Stats {
+            total: 0,
+            histogram: [0; 22],
+            tick: 0,
+            tick_period,
+            max_cycles,
+        }
+    }
+
+    /// Whether the next instruction would exceed `--max-cycles`.
+    pub fn over_budget(&self) -> bool {
+        matches!(self.max_cycles, Some(limit) if self.total >= limit)
+    }
+
+    pub fn max_cycles(&self) -> Option<u64> {
+        self.max_cycles
+    }
+
+    /// Record one dispatch of `opcode` and advance the wrap-around tick.
+    pub fn record(&mut self, opcode: u16) {
+        self.total += 1;
+        if (opcode as usize) < self.histogram.len() {
+            self.histogram[opcode as usize] += 1;
+        }
+        self.tick += 1;
+        if self.tick >= self.tick_period {
+            self.tick = 0;
+        }
+    }
+
+    /// Print the total cycle count and a per-opcode execution histogram.
+    pub fn report(&self) {
+        println!(
+            "Executed {} instructions (tick {} of period {})",
+            self.total, self.tick, self.tick_period
+        );
+        for (opcode, count) in self.histogram.iter().enumerate() {
+            if *count == 0 {
+                continue;
+            }
+            let mnemonic = disasm::opcode_info(opcode as u16)
+                .map(|(m, _)| m)
+                .unwrap_or("??");
+            println!("  {:<6} {}", mnemonic, count);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tick_wraps_around_at_the_period() {
+        let mut stats = Stats::new(None, 3);
+        stats.record(0);
+        stats.record(0);
+        assert_eq!(stats.tick, 2);
+        stats.record(0);
+        assert_eq!(stats.tick, 0);
+    }
+
+    #[test]
+    fn over_budget_once_total_reaches_max_cycles() {
+        let mut stats = Stats::new(Some(2), 1000);
+        assert!(!stats.over_budget());
+        stats.record(0);
+        assert!(!stats.over_budget());
+        stats.record(0);
+        assert!(stats.over_budget());
+    }
+
+    #[test]
+    fn no_budget_never_trips_over_budget() {
+        let mut stats = Stats::new(None, 1000);
+        for _ in 0..10_000 {
+            stats.record(0);
+        }
+        assert!(!stats.over_budget());
+    }
+}